@@ -1,5 +1,6 @@
-use lazy_static::lazy_static;
+use nix::sys::statvfs::statvfs;
 use regex::Regex;
+use serde::Serialize;
 use std::{
     collections::HashMap,
     fs::{self, File},
@@ -56,20 +57,30 @@ impl MountInfo {
     }
 }
 
-lazy_static! {
-    static ref CACAHED_MOUNT_INFO: MountInfo = MountInfo::parse();
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DiskKind {
+    Hdd,
+    Ssd,
+    Flash,
+    Unknown,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct PartitionDevice {
     pub dev_path: String,
 
     pub partition_label: Option<String>,
+    pub partition_uuid: Option<String>,
+    pub partition_partlabel: Option<String>,
     pub partition_filesystem: String,
     pub partition_size: u64,
     pub usb_model_name: Option<String>,
+    pub disk_kind: DiskKind,
 
     pub mounted_points: Vec<String>,
+
+    pub total_space: Option<u64>,
+    pub available_space: Option<u64>,
 }
 
 impl PartitionDevice {
@@ -84,6 +95,21 @@ impl PartitionDevice {
         return device_properties;
     }
 
+    fn get_space_info(mounted_points: &Vec<String>) -> (Option<u64>, Option<u64>) {
+        let mount_point = match mounted_points.first() {
+            Some(mount_point) => mount_point,
+            None => return (None, None), // not mounted, statvfs needs a mount point
+        };
+
+        return match statvfs(mount_point.as_str()) {
+            Ok(stat) => (
+                Some((stat.block_size() as u64).saturating_mul(stat.blocks() as u64)),
+                Some((stat.block_size() as u64).saturating_mul(stat.blocks_available() as u64)),
+            ),
+            Err(_) => (None, None),
+        };
+    }
+
     fn check_is_usb_device(device: &Device) -> bool {
         return if let Ok(parent) = device.parent_with_subsystem("usb") {
             parent.is_some()
@@ -92,7 +118,48 @@ impl PartitionDevice {
         };
     }
 
-    fn from_device(device: Device) -> Option<Self> {
+    // walk up from a partition to the disk it belongs to, e.g. `/dev/sda1` -> `/dev/sda`
+    fn find_parent_disk(device: &Device) -> Option<Device> {
+        let mut current = device.parent()?;
+        loop {
+            if current.devtype().map(|t| t == "disk").unwrap_or(false) {
+                return Some(current);
+            }
+            current = current.parent()?;
+        }
+    }
+
+    fn get_disk_kind(device: &Device) -> DiskKind {
+        let parent_disk = match PartitionDevice::find_parent_disk(device) {
+            Some(parent_disk) => parent_disk,
+            None => return DiskKind::Unknown,
+        };
+
+        let rotational = parent_disk
+            .attribute_value("queue/rotational")
+            .and_then(|v| v.to_string_lossy().parse::<u8>().ok());
+
+        return match rotational {
+            Some(1) => DiskKind::Hdd,
+            Some(0) => {
+                let device_properties = PartitionDevice::get_device_properties(&parent_disk);
+                let removable = parent_disk
+                    .attribute_value("removable")
+                    .and_then(|v| v.to_string_lossy().parse::<u8>().ok());
+
+                if device_properties.get("ID_BUS").map(|s| s.as_str()) == Some("usb")
+                    && removable == Some(1)
+                {
+                    DiskKind::Flash
+                } else {
+                    DiskKind::Ssd
+                }
+            }
+            _ => DiskKind::Unknown,
+        };
+    }
+
+    pub fn from_device(device: Device) -> Option<Self> {
         let device_syspath = String::from(device.syspath().to_string_lossy());
         let device_properties = PartitionDevice::get_device_properties(&device);
 
@@ -100,8 +167,22 @@ impl PartitionDevice {
             return None; // no file system detected, return None
         }
 
+        // re-parsed on every call so long-running callers (e.g. the watch daemon) see current mounts
+        let mount_info = MountInfo::parse();
+
         return if PartitionDevice::check_is_usb_device(&device) {
             // usb partition found
+            let mounted_points = mount_info.get_mount_points_by_id(&String::from(
+                device
+                    .attribute_value("dev")
+                    .expect(&format!(
+                        "device id for device `{}` get failed",
+                        device_syspath
+                    ))
+                    .to_string_lossy(),
+            ));
+            let (total_space, available_space) = PartitionDevice::get_space_info(&mounted_points);
+
             Some(PartitionDevice {
                 dev_path: String::from(
                     device
@@ -115,6 +196,10 @@ impl PartitionDevice {
                 partition_label: device_properties
                     .get("ID_FS_LABEL")
                     .map(|s| String::from(s)),
+                partition_uuid: device_properties.get("ID_FS_UUID").map(|s| String::from(s)),
+                partition_partlabel: device_properties
+                    .get("ID_PART_ENTRY_NAME")
+                    .map(|s| String::from(s)),
                 partition_filesystem: String::from(device_properties.get("ID_FS_TYPE").unwrap()),
                 partition_size: device
                     .attribute_value("size")
@@ -124,15 +209,10 @@ impl PartitionDevice {
                     .unwrap()
                     * 512u64,
                 usb_model_name: device_properties.get("ID_MODEL").map(|s| String::from(s)),
-                mounted_points: CACAHED_MOUNT_INFO.get_mount_points_by_id(&String::from(
-                    device
-                        .attribute_value("dev")
-                        .expect(&format!(
-                            "device id for device `{}` get failed",
-                            device_syspath
-                        ))
-                        .to_string_lossy(),
-                )),
+                disk_kind: PartitionDevice::get_disk_kind(&device),
+                mounted_points,
+                total_space,
+                available_space,
             })
         } else if device_properties.get("DM_NAME").is_some() {
             // dm partition found, we need do an extra test to determine whether it is a slave of usb device
@@ -162,6 +242,17 @@ impl PartitionDevice {
                 return None; // dm device's parent not a usb device, return None
             }
 
+            let mounted_points = mount_info.get_mount_points_by_id(&String::from(
+                device
+                    .attribute_value("dev")
+                    .expect(&format!(
+                        "device id for device `{}` get failed",
+                        device_syspath
+                    ))
+                    .to_string_lossy(),
+            ));
+            let (total_space, available_space) = PartitionDevice::get_space_info(&mounted_points);
+
             Some(PartitionDevice {
                 dev_path: String::from(
                     device
@@ -175,6 +266,10 @@ impl PartitionDevice {
                 partition_label: device_properties
                     .get("ID_FS_LABEL")
                     .map(|s| String::from(s)),
+                partition_uuid: device_properties.get("ID_FS_UUID").map(|s| String::from(s)),
+                partition_partlabel: device_properties
+                    .get("ID_PART_ENTRY_NAME")
+                    .map(|s| String::from(s)),
                 partition_filesystem: String::from(device_properties.get("ID_FS_TYPE").unwrap()),
                 partition_size: device
                     .attribute_value("size")
@@ -186,15 +281,10 @@ impl PartitionDevice {
                 usb_model_name: PartitionDevice::get_device_properties(&parent_device)
                     .get("ID_MODEL")
                     .map(|s| String::from(s)),
-                mounted_points: CACAHED_MOUNT_INFO.get_mount_points_by_id(&String::from(
-                    device
-                        .attribute_value("dev")
-                        .expect(&format!(
-                            "device id for device `{}` get failed",
-                            device_syspath
-                        ))
-                        .to_string_lossy(),
-                )),
+                disk_kind: PartitionDevice::get_disk_kind(&parent_device),
+                mounted_points,
+                total_space,
+                available_space,
             })
         } else {
             None // not a usb or dm device, return None
@@ -1,11 +1,20 @@
 use byte_unit::Byte;
-use dialoguer::{theme::ColorfulTheme, Select};
-use std::{collections::HashMap, fs, path::Path};
+use dialoguer::{theme::ColorfulTheme, Password, Select};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    os::unix::fs::MetadataExt,
+    path::Path,
+    process::{Command, Stdio},
+};
 use sys_mount::{unmount, Mount, MountFlags, SupportedFilesystems, UnmountFlags};
 
-use usbmount::{get_available_partition_devices, PartitionDevice};
+use usbmount::{get_available_partition_devices, DiskKind, PartitionDevice};
 use clap::{Parser, Subcommand};
 use console::Term;
+use udev::{Device, DeviceType, EventType, MonitorBuilder};
+use serde_json::json;
 
 #[derive(Parser)]
 #[clap(version = "1.0")]
@@ -28,6 +37,15 @@ enum Commands {
         #[clap(short, long)]
         mount_option: Option<String>,
 
+        #[clap(short, long)]
+        key_file: Option<String>,
+
+        #[clap(short, long, parse(from_flag))]
+        read_only: bool,
+
+        #[clap(short, long, parse(from_flag))]
+        json: bool,
+
         #[clap(action)]
         dev_path: Option<String>,
 
@@ -37,16 +55,37 @@ enum Commands {
 
     #[clap(visible_alias = "u")]
     Umount {
+        #[clap(short, long, parse(from_flag))]
+        json: bool,
+
         #[clap(action)]
         dev_path: Option<String>,
     },
 
     #[clap(visible_alias = "i")]
-    Info {},
+    Info {
+        #[clap(short, long, parse(from_flag))]
+        json: bool,
+    },
+
+    #[clap(visible_alias = "w")]
+    Watch {
+        #[clap(short, long, default_value_t = String::from("/var/run/media/"))]
+        auto_mount_dir: String,
+
+        #[clap(short, long)]
+        mount_option: Option<String>,
+
+        // watch has no TTY to prompt on, so LUKS containers can only be unlocked non-interactively
+        #[clap(short, long)]
+        key_file: Option<String>,
+    },
 }
 
 static IDENTIFY_FILE: &str = ".create_by_usbmount";
 static MOUNT_WITH_DEFAULT_OPTION_FILESYSTEM: [&str; 3] = ["ntfs", "vfat", "exfat"];
+static LUKS_FILESYSTEM: &str = "crypto_LUKS";
+static LUKS_MAPPER_PREFIX: &str = "usbmount-";
 
 extern "system" {
     fn geteuid() -> u32;
@@ -74,6 +113,26 @@ fn format_partition_size(size: u64) -> String {
     return format!("\"{}\"", result.get_appropriate_unit(true).to_string());
 }
 
+fn format_used_space(total_space: &Option<u64>, available_space: &Option<u64>) -> String {
+    return match (total_space, available_space) {
+        (Some(total_space), Some(available_space)) => format!(
+            "\"{} / {}\"",
+            format_partition_size(total_space.saturating_sub(*available_space)).trim_matches('"'),
+            format_partition_size(*total_space).trim_matches('"'),
+        ),
+        _ => String::from("None"),
+    };
+}
+
+fn format_disk_kind(disk_kind: &DiskKind) -> &'static str {
+    match disk_kind {
+        DiskKind::Hdd => "HDD",
+        DiskKind::Ssd => "SSD",
+        DiskKind::Flash => "Flash",
+        DiskKind::Unknown => "Unknown",
+    }
+}
+
 fn format_mount_points(mount_points: &Vec<String>) -> String {
     let mut ret = String::from("[");
     mount_points
@@ -86,6 +145,214 @@ fn format_mount_points(mount_points: &Vec<String>) -> String {
     return ret;
 }
 
+fn resolve_dev_path(dev_path: &str, devices_map: &HashMap<String, PartitionDevice>) -> Option<String> {
+    let find_by = |matches: &dyn Fn(&PartitionDevice) -> bool| -> Option<String> {
+        devices_map
+            .values()
+            .find(|device| matches(*device))
+            .map(|device| device.dev_path.clone())
+    };
+
+    if let Some(uuid) = dev_path.strip_prefix("UUID=") {
+        return find_by(&|device| device.partition_uuid.as_deref() == Some(uuid));
+    } else if let Some(label) = dev_path.strip_prefix("LABEL=") {
+        return find_by(&|device| device.partition_label.as_deref() == Some(label));
+    } else if let Some(partlabel) = dev_path.strip_prefix("PARTLABEL=") {
+        return find_by(&|device| device.partition_partlabel.as_deref() == Some(partlabel));
+    } else if devices_map.contains_key(dev_path) {
+        return Some(String::from(dev_path));
+    } else {
+        return None;
+    }
+}
+
+fn device_from_devnode(devnode: &str) -> Result<Device, String> {
+    let metadata =
+        fs::metadata(devnode).map_err(|why| format!("stat `{}` error: {}", devnode, why))?;
+    Device::from_devnum(DeviceType::Block, metadata.rdev())
+        .map_err(|why| format!("udev lookup `{}` error: {}", devnode, why))
+}
+
+// ID_FS_TYPE is populated asynchronously by udev's blkid rule once the mapper node shows up,
+// so poll for it instead of assuming it's already there right after `cryptsetup luksOpen` returns.
+fn wait_for_mapper_partition(mapper_path: &str) -> Result<PartitionDevice, String> {
+    let _ = Command::new("udevadm").args(["settle"]).status();
+
+    for _ in 0..20 {
+        let device = device_from_devnode(mapper_path)?;
+        match PartitionDevice::from_device(device) {
+            Some(partition) => return Ok(partition),
+            None => std::thread::sleep(std::time::Duration::from_millis(100)),
+        }
+    }
+
+    Err(format!(
+        "mapper device `{}` has no recognizable file system",
+        mapper_path
+    ))
+}
+
+fn luks_open(dev_path: &str, key_file: &Option<String>) -> Result<String, String> {
+    let mapper_name = format!(
+        "{}{}",
+        LUKS_MAPPER_PREFIX,
+        Path::new(dev_path).file_name().unwrap().to_string_lossy()
+    );
+
+    let passphrase = if let Some(key_file) = key_file {
+        fs::read(key_file).map_err(|why| format!("key file `{}` read error: {}", key_file, why))?
+    } else {
+        Password::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("passphrase for `{}`", dev_path))
+            .interact()
+            .map_err(|why| format!("passphrase prompt error: {}", why))?
+            .into_bytes()
+    };
+
+    let mut child = Command::new("cryptsetup")
+        .args(["luksOpen", dev_path, &mapper_name, "--key-file", "-"])
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|why| format!("cryptsetup spawn error: {}", why))?;
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(&passphrase)
+        .map_err(|why| format!("cryptsetup stdin write error: {}", why))?;
+
+    let status = child
+        .wait()
+        .map_err(|why| format!("cryptsetup wait error: {}", why))?;
+
+    if !status.success() {
+        return Err(format!("cryptsetup luksOpen `{}` failed", dev_path));
+    }
+
+    Ok(mapper_name)
+}
+
+fn luks_close(mapper_name: &str) -> Result<(), String> {
+    let status = Command::new("cryptsetup")
+        .args(["luksClose", mapper_name])
+        .status()
+        .map_err(|why| format!("cryptsetup spawn error: {}", why))?;
+
+    if !status.success() {
+        return Err(format!("cryptsetup luksClose `{}` failed", mapper_name));
+    }
+
+    Ok(())
+}
+
+fn parse_mount_flags(mount_option: &str) -> (MountFlags, String) {
+    let mut flags = MountFlags::empty();
+    let mut data_tokens: Vec<&str> = Vec::new();
+
+    for token in mount_option.split(',').filter(|token| !token.is_empty()) {
+        match token {
+            "ro" => flags |= MountFlags::RDONLY,
+            "noexec" => flags |= MountFlags::NOEXEC,
+            "nosuid" => flags |= MountFlags::NOSUID,
+            "nodev" => flags |= MountFlags::NODEV,
+            "noatime" => flags |= MountFlags::NOATIME,
+            _ => data_tokens.push(token), // not a recognized flag, pass through as data
+        }
+    }
+
+    (flags, data_tokens.join(","))
+}
+
+fn mount_device(
+    device: PartitionDevice,
+    auto_mount_dir: &str,
+    mount_option: &Option<String>,
+    read_only: bool,
+    mount_path: &Option<String>,
+) -> Result<String, String> {
+    let mount_path = if let Some(mount_path) = mount_path {
+        String::from(mount_path)
+    } else {
+        let base_dir = if device.partition_label.is_some() {
+            device.partition_label.unwrap()
+        } else {
+            String::from(
+                Path::new(&device.dev_path)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy(),
+            )
+        };
+
+        let username = std::env::var("SUDO_USER").unwrap_or(whoami::username());
+
+        let mut mount_path = Path::new(&auto_mount_dir).join(username).join(base_dir);
+        if mount_path.exists() {
+            let mut deduplicate_id = 0;
+            loop {
+                let new_mount_path =
+                    format!("{}-{}", mount_path.to_str().unwrap(), deduplicate_id);
+                let new_mount_path = Path::new(&new_mount_path);
+
+                if new_mount_path.exists() {
+                    deduplicate_id = deduplicate_id + 1;
+                } else {
+                    mount_path = new_mount_path.to_path_buf();
+                    break;
+                }
+            }
+        }
+        fs::create_dir_all(mount_path.clone()).expect(&format!(
+            "mount path `{}` create error",
+            mount_path.to_string_lossy()
+        ));
+
+        // create a speacil file so we can identify the dirctory we created
+        fs::File::create(mount_path.join(IDENTIFY_FILE)).expect(&format!(
+            "create identify file `{}` error",
+            mount_path.join(IDENTIFY_FILE).to_string_lossy()
+        ));
+
+        String::from(mount_path.to_string_lossy())
+    };
+
+    let (mut mount_flags, mount_option) = if let Some(mount_option) = mount_option {
+        parse_mount_flags(mount_option)
+    } else if MOUNT_WITH_DEFAULT_OPTION_FILESYSTEM.contains(&device.partition_filesystem.as_str()) {
+        // foreign filesystems on untrusted removable media: deny setuid/device nodes by default
+        (
+            MountFlags::NOSUID | MountFlags::NODEV,
+            format!(
+                "uid={},gid={}",
+                std::env::var("SUDO_UID").unwrap_or(safe_geteuid().to_string()),
+                std::env::var("SUDO_GID").unwrap_or(safe_getegid().to_string())
+            ),
+        )
+    } else {
+        (MountFlags::empty(), String::new())
+    };
+
+    if read_only {
+        mount_flags |= MountFlags::RDONLY;
+    }
+
+    // mount device
+    match Mount::new(
+        &device.dev_path,
+        &mount_path,
+        &SupportedFilesystems::new().unwrap(),
+        mount_flags,
+        Some(&mount_option),
+    ) {
+        Ok(_) => Ok(mount_path),
+        Err(why) => Err(format!(
+            "failed to mount {} to {}: {}",
+            device.dev_path, mount_path, why
+        )),
+    }
+}
+
 fn select_mount_device(mut devices_map: HashMap<String, PartitionDevice>) -> PartitionDevice {
     let mut device_vec: Vec<&PartitionDevice> = devices_map
         .values()
@@ -136,11 +403,12 @@ fn select_umount_device(mut devices_map: HashMap<String, PartitionDevice>) -> Pa
         .iter()
         .map(|device| {
             format!(
-                "{} [MountPoint({}) FileSystem({}) Size({}) Label({}) Model({})]",
+                "{} [MountPoint({}) FileSystem({}) Size({}) Used({}) Label({}) Model({})]",
                 device.dev_path,
                 format_mount_points(&device.mounted_points),
                 format_optional_string(&Some(device.partition_filesystem.clone())),
                 format_partition_size(device.partition_size),
+                format_used_space(&device.total_space, &device.available_space),
                 format_optional_string(&device.partition_label),
                 format_optional_string(&device.usb_model_name),
             )
@@ -174,7 +442,7 @@ fn main() {
 
     let argument_parser = ArgumentParser::parse();
     match argument_parser.command {
-        Commands::Mount{..} | Commands::Umount{..} => {
+        Commands::Mount{..} | Commands::Umount{..} | Commands::Watch{..} => {
             if !argument_parser.skip_escalate && safe_geteuid() != 0 {
                 sudo::escalate_if_needed().expect("escalate error");
             }
@@ -191,6 +459,9 @@ fn main() {
         Commands::Mount {
             auto_mount_dir,
             mount_option,
+            key_file,
+            read_only,
+            json,
             dev_path,
             mount_path,
         } => {
@@ -210,21 +481,44 @@ fn main() {
                     select_mount_device(devices_map)
                 }
                 (Some(dev_path), _) => {
-                    // dev_path provided
-                    if !devices_map.contains_key(dev_path) {
+                    // dev_path provided, resolve UUID=/LABEL=/PARTLABEL= identifiers as well
+                    let resolved_dev_path = resolve_dev_path(dev_path, &devices_map);
+                    if resolved_dev_path.is_none() {
                         eprintln!(
                             "device `{}` not exist or its not a portable block device",
                             dev_path
                         );
                         std::process::exit(-1);
                     }
-                    devices_map.remove(dev_path).unwrap()
+                    devices_map.remove(&resolved_dev_path.unwrap()).unwrap()
                 }
                 (_, _) => {
                     panic!("unexpected status")
                 }
             };
 
+            let device = if device.partition_filesystem == LUKS_FILESYSTEM {
+                // LUKS container, unlock it and mount the opened mapper device instead
+                let mapper_name = match luks_open(&device.dev_path, key_file) {
+                    Ok(mapper_name) => mapper_name,
+                    Err(why) => {
+                        eprintln!("{}", why);
+                        std::process::exit(-1);
+                    }
+                };
+                let mapper_path = format!("/dev/mapper/{}", mapper_name);
+
+                match wait_for_mapper_partition(&mapper_path) {
+                    Ok(mapper_partition) => mapper_partition,
+                    Err(why) => {
+                        eprintln!("{}", why);
+                        std::process::exit(-1);
+                    }
+                }
+            } else {
+                device
+            };
+
             if device.mounted_points.len() > 0 {
                 eprintln!(
                     "device `{}` alreday mounted at `{}`",
@@ -232,90 +526,26 @@ fn main() {
                 );
                 std::process::exit(-1);
             } else {
-                let mount_path = if let Some(mount_path) = mount_path {
-                    String::from(mount_path)
-                } else {
-                    let base_dir = if device.partition_label.is_some() {
-                        device.partition_label.unwrap()
-                    } else {
-                        String::from(
-                            Path::new(&device.dev_path)
-                                .file_name()
-                                .unwrap()
-                                .to_string_lossy(),
-                        )
-                    };
-
-                    let username = std::env::var("SUDO_USER").unwrap_or(whoami::username());
-
-                    let mut mount_path = Path::new(&auto_mount_dir).join(username).join(base_dir);
-                    if mount_path.exists() {
-                        let mut deduplicate_id = 0;
-                        loop {
-                            let new_mount_path =
-                                format!("{}-{}", mount_path.to_str().unwrap(), deduplicate_id);
-                            let new_mount_path = Path::new(&new_mount_path);
-
-                            if new_mount_path.exists() {
-                                deduplicate_id = deduplicate_id + 1;
-                            } else {
-                                mount_path = new_mount_path.to_path_buf();
-                                break;
-                            }
+                let dev_path = device.dev_path.clone();
+                match mount_device(device, auto_mount_dir, mount_option, *read_only, mount_path) {
+                    Ok(mount_path) => {
+                        if *json {
+                            println!(
+                                "{}",
+                                json!({"device": dev_path, "mount_point": mount_path})
+                            );
+                        } else {
+                            println!("{}", mount_path);
                         }
                     }
-                    fs::create_dir_all(mount_path.clone()).expect(&format!(
-                        "mount path `{}` create error",
-                        mount_path.to_string_lossy()
-                    ));
-
-                    // create a speacil file so we can identify the dirctory we created
-                    fs::File::create(mount_path.join(IDENTIFY_FILE)).expect(&format!(
-                        "create identify file `{}` error",
-                        mount_path.join(IDENTIFY_FILE).to_string_lossy()
-                    ));
-
-                    String::from(mount_path.to_string_lossy())
-                };
-
-                let mount_option = if let Some(mount_option) = mount_option {
-                    String::from(mount_option)
-                } else {
-                    if MOUNT_WITH_DEFAULT_OPTION_FILESYSTEM
-                        .contains(&device.partition_filesystem.as_str())
-                    {
-                        format!(
-                            "uid={},gid={}",
-                            std::env::var("SUDO_UID").unwrap_or(safe_geteuid().to_string()),
-                            std::env::var("SUDO_GID").unwrap_or(safe_getegid().to_string())
-                        )
-                    } else {
-                        String::new()
-                    }
-                };
-
-                // mount device
-                match Mount::new(
-                    &device.dev_path,
-                    &mount_path,
-                    &SupportedFilesystems::new().unwrap(),
-                    MountFlags::empty(),
-                    Some(&mount_option),
-                ) {
-                    Ok(_) => {
-                        println!("{}", mount_path);
-                    }
                     Err(why) => {
-                        eprintln!(
-                            "failed to mount {} to {}: {}",
-                            device.dev_path, mount_path, why
-                        );
+                        eprintln!("{}", why);
                         std::process::exit(-1);
                     }
                 }
             }
         }
-        Commands::Umount { dev_path } => {
+        Commands::Umount { json, dev_path } => {
             let device = match (dev_path, devices_map.len()) {
                 (None, 1) => {
                     // dev_path not provided but only one block device, use it as default
@@ -332,15 +562,16 @@ fn main() {
                     select_umount_device(devices_map)
                 }
                 (Some(dev_path), _) => {
-                    // dev_path provided
-                    if !devices_map.contains_key(dev_path) {
+                    // dev_path provided, resolve UUID=/LABEL=/PARTLABEL= identifiers as well
+                    let resolved_dev_path = resolve_dev_path(dev_path, &devices_map);
+                    if resolved_dev_path.is_none() {
                         eprintln!(
                             "device `{}` not exist or its not a portable block device",
                             dev_path
                         );
                         std::process::exit(-1);
                     }
-                    devices_map.remove(dev_path).unwrap()
+                    devices_map.remove(&resolved_dev_path.unwrap()).unwrap()
                 }
                 (_, _) => {
                     panic!("unexpected status")
@@ -361,7 +592,14 @@ fn main() {
                             result.err().unwrap()
                         );
                     } else {
-                        println!("{}", mount_point);
+                        if *json {
+                            println!(
+                                "{}",
+                                json!({"device": device.dev_path, "mount_point": mount_point})
+                            );
+                        } else {
+                            println!("{}", mount_point);
+                        }
                         let identify_file_path = Path::new(&mount_point).join(IDENTIFY_FILE);
                         if identify_file_path.exists() {
                             fs::remove_file(&identify_file_path).expect(&format!(
@@ -375,20 +613,159 @@ fn main() {
                         }
                     }
                 }
+
+                // close the mapper we opened for this LUKS container, if any, after trying to
+                // unmount all its mount points; dev_path of a dm device is `/dev/dm-N`, so the
+                // mapper name is read from its DM_NAME property
+                let mapper_name = device_from_devnode(&device.dev_path).ok().and_then(|dm| {
+                    dm.property_value("DM_NAME")
+                        .map(|name| name.to_string_lossy().into_owned())
+                });
+                if let Some(mapper_name) = mapper_name {
+                    if mapper_name.starts_with(LUKS_MAPPER_PREFIX) {
+                        if let Err(why) = luks_close(&mapper_name) {
+                            eprintln!("{}", why);
+                        }
+                    }
+                }
             }
         }
-        Commands::Info {} => {
-            devices_map.values().for_each(|device| {
+        Commands::Info { json } => {
+            if *json {
+                let devices: Vec<&PartitionDevice> = devices_map.values().collect();
                 println!(
-                    "{} [MountPoint({}) FileSystem({}) Size({}) Label({}) Model({})]",
-                    device.dev_path,
-                    format_mount_points(&device.mounted_points),
-                    format_optional_string(&Some(device.partition_filesystem.clone())),
-                    format_partition_size(device.partition_size),
-                    format_optional_string(&device.partition_label),
-                    format_optional_string(&device.usb_model_name),
+                    "{}",
+                    serde_json::to_string(&devices).expect("device list json serialize error")
                 );
-            });
+            } else {
+                devices_map.values().for_each(|device| {
+                    println!(
+                        "{} [MountPoint({}) FileSystem({}) Size({}) Used({}) Label({}) Model({}) Type({})]",
+                        device.dev_path,
+                        format_mount_points(&device.mounted_points),
+                        format_optional_string(&Some(device.partition_filesystem.clone())),
+                        format_partition_size(device.partition_size),
+                        format_used_space(&device.total_space, &device.available_space),
+                        format_optional_string(&device.partition_label),
+                        format_optional_string(&device.usb_model_name),
+                        format_disk_kind(&device.disk_kind),
+                    );
+                });
+            }
+        }
+        Commands::Watch {
+            auto_mount_dir,
+            mount_option,
+            key_file,
+        } => {
+            let socket = MonitorBuilder::new()
+                .expect("udev monitor create failed")
+                .match_subsystem("block")
+                .expect("udev monitor filter failed")
+                .listen()
+                .expect("udev monitor listen failed");
+
+            println!(
+                "watching for usb block devices, auto mounting under `{}`...",
+                auto_mount_dir
+            );
+
+            // raw (pre-LUKS-unlock) dev_path -> (mount_path, mapper_name), so a later remove
+            // event, which always fires on the raw device, can clean up what we mounted even
+            // when the actually-mounted device was a dm-crypt mapper opened on its behalf
+            let mut auto_mounted: HashMap<String, (String, Option<String>)> = HashMap::new();
+
+            for event in socket.iter() {
+                match event.event_type() {
+                    EventType::Add => {
+                        if let Some(device) = PartitionDevice::from_device(event.device()) {
+                            if device.mounted_points.len() > 0 {
+                                continue;
+                            }
+
+                            let raw_dev_path = device.dev_path.clone();
+
+                            let (device, mapper_name) = if device.partition_filesystem
+                                == LUKS_FILESYSTEM
+                            {
+                                if key_file.is_none() {
+                                    eprintln!(
+                                        "device `{}` is LUKS-encrypted and no --key-file was given, skipping",
+                                        device.dev_path
+                                    );
+                                    continue;
+                                }
+
+                                let mapper_name = match luks_open(&device.dev_path, key_file) {
+                                    Ok(mapper_name) => mapper_name,
+                                    Err(why) => {
+                                        eprintln!("{}", why);
+                                        continue;
+                                    }
+                                };
+                                let mapper_path = format!("/dev/mapper/{}", mapper_name);
+
+                                match wait_for_mapper_partition(&mapper_path) {
+                                    Ok(mapper_partition) => (mapper_partition, Some(mapper_name)),
+                                    Err(why) => {
+                                        eprintln!("{}", why);
+                                        continue;
+                                    }
+                                }
+                            } else {
+                                (device, None)
+                            };
+
+                            match mount_device(device, auto_mount_dir, mount_option, false, &None) {
+                                Ok(mount_path) => {
+                                    println!("{} mounted at {}", raw_dev_path, mount_path);
+                                    auto_mounted.insert(raw_dev_path, (mount_path, mapper_name));
+                                }
+                                Err(why) => eprintln!("{}", why),
+                            }
+                        }
+                    }
+                    EventType::Remove => {
+                        let dev_path = event
+                            .device()
+                            .devnode()
+                            .map(|p| String::from(p.to_string_lossy()));
+
+                        if let Some((mount_path, mapper_name)) =
+                            dev_path.and_then(|dev_path| auto_mounted.remove(&dev_path))
+                        {
+                            let result = unmount(&mount_path, UnmountFlags::empty());
+                            if result.is_err() {
+                                eprintln!(
+                                    "when umount mount point `{}`, encount error `{}`",
+                                    &mount_path,
+                                    result.err().unwrap()
+                                );
+                            } else {
+                                let identify_file_path = Path::new(&mount_path).join(IDENTIFY_FILE);
+                                if identify_file_path.exists() {
+                                    fs::remove_file(&identify_file_path).expect(&format!(
+                                        "remove identify file `{}` error",
+                                        identify_file_path.to_string_lossy()
+                                    ));
+                                    fs::remove_dir(&mount_path).expect(&format!(
+                                        "remove mount point directory `{}` error",
+                                        mount_path
+                                    ));
+                                }
+                                println!("{}", mount_path);
+
+                                if let Some(mapper_name) = mapper_name {
+                                    if let Err(why) = luks_close(&mapper_name) {
+                                        eprintln!("{}", why);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
         }
     }
 }